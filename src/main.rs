@@ -7,9 +7,10 @@ use crossterm::{
     ExecutableCommand, QueueableCommand,
 };
 use opencv::imgproc;
-use opencv::{core, prelude::*, videoio};
-use opencv::core::{Size, Vec3b};
+use opencv::{core, imgcodecs, prelude::*, videoio};
+use opencv::core::{Scalar, Size, Vec3b};
 use std::io::{stdout, Write};
+use std::path::Path;
 use rand::Rng;
 use eframe::egui;
 
@@ -27,6 +28,8 @@ pub enum CharSet {
     Slashed,
     Testing,
     Testing2,
+    /// User-supplied ramp, see `--characters`
+    Custom,
 }
 
 impl CharSet {
@@ -42,10 +45,15 @@ impl CharSet {
             CharSet::Modern => CharSet::Slashed,
             CharSet::Slashed => CharSet::Testing,
             CharSet::Testing => CharSet::Testing2,
-            CharSet::Testing2 => CharSet::Retro,
+            CharSet::Testing2 => CharSet::Custom,
+            CharSet::Custom => CharSet::Retro,
         }
     }
 
+    /// Ramp for every built-in charset. `Custom` has no ramp of its own — its
+    /// text comes from `--characters` instead — so this returns the default
+    /// ramp as a harmless placeholder; callers that care about `--characters`
+    /// should go through `resolve_charset` instead of calling this directly.
     pub fn get_chars(&self) -> &'static str {
         match self {
             CharSet::Retro => " ░▒▓█",
@@ -61,10 +69,48 @@ impl CharSet {
             CharSet::Slashed => " /\\|",
             CharSet::Testing2 => "01",
             CharSet::Detailed => "$@B%8&WM #*oahkbdpqwmZO0QLCJUYXzcvunxrjft/()1{}[]?-_+~<>i!lI;:,",
+            CharSet::Custom => "@%#*+=-:.",
         }
     }
 }
 
+/// Cap on `--characters`, generous enough for any realistic ramp while
+/// keeping the `lum -> index` math in `assign_chars` sane.
+const MAX_CUSTOM_CHARS: usize = 64;
+
+/// Validates `--characters` the way a clap `value_parser` would: non-empty,
+/// within `MAX_CUSTOM_CHARS`, and long enough to carry luminance information
+/// unless a color mode supplies that information instead.
+fn validate_custom_chars(characters: &str, mode: ArtMode) -> anyhow::Result<()> {
+    if characters.is_empty() {
+        return Err(anyhow::anyhow!("--characters must not be empty"));
+    }
+
+    if characters.chars().count() > MAX_CUSTOM_CHARS {
+        return Err(anyhow::anyhow!("--characters must be at most {} characters", MAX_CUSTOM_CHARS));
+    }
+
+    // Rainbow derives color purely from cell position/frame count, not the
+    // sampled pixel, so a single glyph there really does erase the source
+    // image. Every other mode colors by (or alongside) the sample itself.
+    if characters.chars().count() == 1 && mode == ArtMode::Rainbow {
+        return Err(anyhow::anyhow!(
+            "a single-character --characters ramp carries no image information in --mode Rainbow; pick a different --mode"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Resolves the char ramp to render with, honoring `--characters` when
+/// `charset` is `CharSet::Custom`.
+fn resolve_charset<'a>(charset: CharSet, characters: &'a Option<String>) -> &'a str {
+    match charset {
+        CharSet::Custom => characters.as_deref().unwrap_or(" .:-=+*#%@"),
+        other => other.get_chars(),
+    }
+}
+
 #[derive(Clone, ValueEnum, Default, PartialEq, Debug, Copy)]
 pub enum ArtMode {
     #[default] Standard,   // Original Colors
@@ -76,6 +122,7 @@ pub enum ArtMode {
     Rainbow,    // Animated Rainbow
     Cga,        // Cyan/Magenta/White/Black
     Glitch,     // Random artifacts
+    Indexed,    // Quantized retro palette (LBG/VQ codebook)
 }
 
 impl ArtMode {
@@ -89,11 +136,29 @@ impl ArtMode {
             ArtMode::Neon => ArtMode::Rainbow,
             ArtMode::Rainbow => ArtMode::Cga,
             ArtMode::Cga => ArtMode::Glitch,
-            ArtMode::Glitch => ArtMode::Standard,
+            ArtMode::Glitch => ArtMode::Indexed,
+            ArtMode::Indexed => ArtMode::Standard,
         }
     }
 }
 
+/// Corner `--caption` is anchored to.
+#[derive(Clone, ValueEnum, Default, PartialEq, Debug, Copy)]
+pub enum CaptionPos {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// Squared-RGB-distance a cell must clear before it's redrawn; 0 is most
+/// tolerant (color changes under 80 skip when the glyph is unchanged), 100
+/// redraws every cell.
+fn skip_threshold(quality: u8) -> i32 {
+    (10 - (quality as i32 / 10).min(10)) * 8
+}
+
 #[derive(Parser, Clone)]
 pub struct Args {
     /// Charset to use
@@ -116,6 +181,16 @@ pub struct Args {
     #[arg(short, long)]
     pub input: Option<String>,
 
+    /// Path to a still image to convert, as an alias for --input that reads
+    /// more naturally for one-shot image conversion
+    #[arg(long)]
+    pub image: Option<String>,
+
+    /// Downsample factor for --output <file>.txt: output width is the
+    /// source frame's width divided by this value
+    #[arg(long, default_value_t = 4)]
+    pub scale: u32,
+
     /// Flip the image horizontally
     #[arg(long, default_value_t = false)]
     pub flip: bool,
@@ -123,6 +198,73 @@ pub struct Args {
     /// Render to terminal directly
     #[arg(long, default_value_t = false)]
     pub terminal: bool,
+
+    /// Rendering quality 0..=100; lower values skip redrawing cells that
+    /// barely changed, cutting bytes written to stdout for static scenes
+    #[arg(long, default_value_t = 100)]
+    pub quality: u8,
+
+    /// In --terminal mode, wrap each glyph in a manual 24-bit ANSI escape
+    /// (falling back to 256-color) instead of going through crossterm
+    #[arg(long, default_value_t = false)]
+    pub color: bool,
+
+    /// Render the ASCII art back to an image/video file instead of a live
+    /// display (extension picks the codec: .png for a still, else mp4v/MJPG)
+    #[arg(long)]
+    pub output: Option<String>,
+
+    /// Frame rate for --output video, --export-web playback, or --record (--play
+    /// replays the delays stored in the file); must be 1..=480
+    #[arg(long, default_value_t = 30.0)]
+    pub fps: f64,
+
+    /// Codebook size for `--mode Indexed`, rounded up to the next power of two
+    #[arg(long, default_value_t = 16)]
+    pub palette_size: u32,
+
+    /// Sample each cell as a 2x2 sub-pixel grid and render it as a Unicode
+    /// quadrant glyph, roughly doubling perceived resolution
+    #[arg(long, default_value_t = false)]
+    pub subcell: bool,
+
+    /// Export the colored ASCII animation as a standalone SVG (single image)
+    /// or replayable HTML document (video/camera) instead of a live display
+    #[arg(long)]
+    pub export_web: Option<String>,
+
+    /// Custom char ramp (lightest to darkest) used when --charset Custom
+    #[arg(long)]
+    pub characters: Option<String>,
+
+    /// Record the rendered ASCII frames (with per-frame delay) to a replayable file
+    #[arg(long)]
+    pub record: Option<String>,
+
+    /// Replay a file previously written by --record
+    #[arg(long)]
+    pub play: Option<String>,
+
+    /// Cap a --record session to this many milliseconds
+    #[arg(long)]
+    pub duration: Option<u64>,
+
+    /// Text to burn into the frame before ASCII conversion, so it shows up
+    /// as legible glyphs in the output art (e.g. a watermark or a label)
+    #[arg(long)]
+    pub caption: Option<String>,
+
+    /// Corner to anchor --caption in
+    #[arg(long, value_enum, default_value_t = CaptionPos::BottomRight)]
+    pub caption_pos: CaptionPos,
+}
+
+impl Args {
+    /// `--image` is preferred when both are given, since it's the more
+    /// specific flag for the still-image conversion path.
+    pub fn source_path(&self) -> Option<&str> {
+        self.image.as_deref().or(self.input.as_deref())
+    }
 }
 
 #[derive(Default, Clone, Copy)]
@@ -168,6 +310,119 @@ pub fn calculate_block_size(img_width: i32, width: i32) -> (u32, u32) {
     (block_w.round() as u32, block_h.round() as u32)
 }
 
+/// Fixed 5x7 bitmap font for `--caption`; each row is the low 5 bits of a u8,
+/// MSB-first. Unknown characters render as a blank cell.
+fn glyph_5x7(ch: char) -> [u8; 7] {
+    match ch.to_ascii_uppercase() {
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01111, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b01111],
+        'D' => [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01111, 0b10000, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        '0' => [0b01110, 0b10011, 0b10101, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        '.' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100],
+        ',' => [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100, 0b01000],
+        '!' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100],
+        '?' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100],
+        ':' => [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000],
+        '-' => [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000],
+        '\'' => [0b01100, 0b01100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000],
+        _ => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+    }
+}
+
+/// Composites `text` onto `frame` as darkened 5x7 glyphs, scaled to roughly
+/// `frame_width / 80` pixels and anchored to `pos`.
+fn burn_caption(frame: &mut Mat, text: &str, pos: CaptionPos) -> opencv::Result<()> {
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    let img_w = frame.cols();
+    let img_h = frame.rows();
+    if img_w <= 0 || img_h <= 0 {
+        return Ok(());
+    }
+
+    let scale = (img_w / 80).max(2);
+    let glyph_w = 5 * scale;
+    let glyph_h = 7 * scale;
+    let gap = scale;
+    let margin = scale * 2;
+
+    let chars: Vec<char> = text.chars().collect();
+    let text_w = chars.len() as i32 * (glyph_w + gap);
+
+    let (ox, oy) = match pos {
+        CaptionPos::TopLeft => (margin, margin),
+        CaptionPos::TopRight => ((img_w - text_w - margin).max(0), margin),
+        CaptionPos::BottomLeft => (margin, (img_h - glyph_h - margin).max(0)),
+        CaptionPos::BottomRight => ((img_w - text_w - margin).max(0), (img_h - glyph_h - margin).max(0)),
+    };
+
+    for (i, ch) in chars.iter().enumerate() {
+        let glyph = glyph_5x7(*ch);
+        let gx = ox + i as i32 * (glyph_w + gap);
+
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..5 {
+                if bits & (1 << (4 - col)) == 0 {
+                    continue;
+                }
+
+                let px0 = gx + col as i32 * scale;
+                let py0 = oy + row as i32 * scale;
+
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let (px, py) = (px0 + dx, py0 + dy);
+                        if px < 0 || py < 0 || px >= img_w || py >= img_h {
+                            continue;
+                        }
+
+                        let pixel = frame.at_2d_mut::<Vec3b>(py, px)?;
+                        pixel[0] = 0;
+                        pixel[1] = 0;
+                        pixel[2] = 0;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 pub fn assign_chars(
     ascii_data: &mut Vec<Vec<(BlockSample, char)>>,
     char_set: &str,
@@ -222,6 +477,147 @@ pub fn assign_chars(
     Ok(())
 }
 
+/// Four luminance/RGB sub-samples for one terminal cell, ordered
+/// top-left, top-right, bottom-left, bottom-right.
+#[derive(Default, Clone, Copy)]
+pub struct SubCell {
+    pub lum: [f32; 4],
+    pub r: [u8; 4],
+    pub g: [u8; 4],
+    pub b: [u8; 4],
+}
+
+/// Same block geometry as `assign_chars`, but samples each cell as a 2x2
+/// grid of sub-pixels instead of collapsing it to a single luminance, for
+/// `--subcell` quadrant rendering.
+pub fn assign_subcells(
+    subcell_data: &mut Vec<Vec<SubCell>>,
+    frame_data: &Mat,
+    width: i32,
+) -> opencv::Result<()> {
+    if frame_data.empty() {
+        return Ok(());
+    }
+
+    let img_w = frame_data.size()?.width;
+    let (block_w, block_h) = calculate_block_size(img_w, width);
+
+    let target_width = (img_w as f32 / block_w as f32).floor() as i32;
+    let target_height = (frame_data.size()?.height as f32 / block_h as f32).floor() as i32;
+
+    if target_width <= 0 || target_height <= 0 {
+        return Ok(());
+    }
+
+    let mut resized = Mat::default();
+    imgproc::resize(
+        frame_data,
+        &mut resized,
+        Size::new(target_width * 2, target_height * 2),
+        0.0,
+        0.0,
+        imgproc::INTER_AREA,
+    )?;
+
+    const OFFSETS: [(i32, i32); 4] = [(0, 0), (0, 1), (1, 0), (1, 1)];
+
+    for y in 0..target_height {
+        let mut row = Vec::with_capacity(target_width as usize);
+        for x in 0..target_width {
+            let mut cell = SubCell::default();
+            for (i, (dy, dx)) in OFFSETS.iter().enumerate() {
+                let pixel = resized.at_2d::<Vec3b>(y * 2 + dy, x * 2 + dx)?;
+                let b = pixel[0];
+                let g = pixel[1];
+                let r = pixel[2];
+
+                cell.lum[i] = r as f32 * WEIGHTS[0] + g as f32 * WEIGHTS[1] + b as f32 * WEIGHTS[2];
+                cell.r[i] = r;
+                cell.g[i] = g;
+                cell.b[i] = b;
+            }
+            row.push(cell);
+        }
+        subcell_data.push(row);
+    }
+
+    Ok(())
+}
+
+/// Picks the Unicode quadrant glyph whose filled corners match `mask`, a
+/// 4-bit TL/TR/BL/BR pattern of which sub-quadrants are brighter than the
+/// cell's mean luminance. The base repertoire has no dedicated three-quarter
+/// glyph, so a 3-bits-set mask (one dark corner) renders as a solid block.
+fn quadrant_glyph(mask: u8) -> char {
+    match mask {
+        0b0000 => ' ',
+        0b1000 => '▘',
+        0b0100 => '▝',
+        0b0010 => '▖',
+        0b0001 => '▗',
+        0b1001 => '▚',
+        0b0110 => '▞',
+        0b1100 => '▀',
+        0b0011 => '▄',
+        0b1010 => '▌',
+        0b0101 => '▐',
+        _ => '█',
+    }
+}
+
+/// Companion to `get_color` for `--subcell` mode: chooses the quadrant glyph
+/// for a `SubCell`, then runs the mean color of the sub-quadrants above (for
+/// the foreground) and below (for the background) the cell's mean luminance
+/// back through `get_color`, so `--subcell` renders in the selected `ArtMode`
+/// instead of always Standard true-color.
+pub fn subcell_glyph_and_colors(
+    cell: &SubCell,
+    mode: &ArtMode,
+    x: usize,
+    y: usize,
+    frame_count: usize,
+    palette: Option<&[(f32, f32, f32)]>,
+) -> (char, (u8, u8, u8), (u8, u8, u8)) {
+    const BITS: [u8; 4] = [0b1000, 0b0100, 0b0010, 0b0001];
+
+    let mean: f32 = cell.lum.iter().sum::<f32>() / 4.0;
+
+    let mut mask = 0u8;
+    let mut bright = (0u32, 0u32, 0u32, 0u32);
+    let mut dark = (0u32, 0u32, 0u32, 0u32);
+
+    for i in 0..4 {
+        let bucket = if cell.lum[i] > mean {
+            mask |= BITS[i];
+            &mut bright
+        } else {
+            &mut dark
+        };
+
+        bucket.0 += cell.r[i] as u32;
+        bucket.1 += cell.g[i] as u32;
+        bucket.2 += cell.b[i] as u32;
+        bucket.3 += 1;
+    }
+
+    let avg_sample = |sums: (u32, u32, u32, u32)| -> BlockSample {
+        if sums.3 == 0 {
+            BlockSample::default()
+        } else {
+            let r = (sums.0 / sums.3) as u8;
+            let g = (sums.1 / sums.3) as u8;
+            let b = (sums.2 / sums.3) as u8;
+            let lum = r as f32 * WEIGHTS[0] + g as f32 * WEIGHTS[1] + b as f32 * WEIGHTS[2];
+            BlockSample { lum, r, g, b }
+        }
+    };
+
+    let fg = get_color(&avg_sample(bright), mode, x, y, frame_count, palette);
+    let bg = get_color(&avg_sample(dark), mode, x, y, frame_count, palette);
+
+    (quadrant_glyph(mask), fg, bg)
+}
+
 // Helper to convert HSV to RGB (Hue 0-360, Sat 0-1, Val 0-1)
 fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (u8, u8, u8) {
     let c = v * s;
@@ -249,7 +645,151 @@ fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (u8, u8, u8) {
     )
 }
 
-fn get_color(sample: &BlockSample, mode: &ArtMode, x: usize, y: usize, frame_count: usize) -> (u8, u8, u8) {
+/// Nearest codebook entry to `(r, g, b)` by squared Euclidean RGB distance,
+/// along with that squared distance (used as the Lloyd-iteration distortion).
+fn nearest_centroid(centroids: &[(f32, f32, f32)], r: u8, g: u8, b: u8) -> (usize, f32) {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, &(cr, cg, cb))| {
+            let dr = r as f32 - cr;
+            let dg = g as f32 - cg;
+            let db = b as f32 - cb;
+            (i, dr * dr + dg * dg + db * db)
+        })
+        .fold((0, f32::MAX), |best, cur| if cur.1 < best.1 { cur } else { best })
+}
+
+/// One round of Lloyd's algorithm: reassign colors to their nearest centroid,
+/// recompute centroids as the mean, repeat until distortion stops decreasing.
+fn lloyd_iterate(colors: &[(u8, u8, u8)], mut centroids: Vec<(f32, f32, f32)>) -> Vec<(f32, f32, f32)> {
+    let mut prev_distortion = f32::MAX;
+
+    loop {
+        let mut sums = vec![(0.0f32, 0.0f32, 0.0f32, 0u32); centroids.len()];
+        let mut distortion = 0.0f32;
+
+        for &(r, g, b) in colors {
+            let (idx, dist) = nearest_centroid(&centroids, r, g, b);
+            distortion += dist;
+            let sum = &mut sums[idx];
+            sum.0 += r as f32;
+            sum.1 += g as f32;
+            sum.2 += b as f32;
+            sum.3 += 1;
+        }
+
+        for (centroid, (sr, sg, sb, count)) in centroids.iter_mut().zip(sums) {
+            if count > 0 {
+                *centroid = (sr / count as f32, sg / count as f32, sb / count as f32);
+            }
+        }
+
+        if prev_distortion - distortion < 1.0 {
+            break;
+        }
+        prev_distortion = distortion;
+    }
+
+    centroids
+}
+
+/// LBG generalized-Lloyd quantization: starting from one centroid (or the
+/// previous frame's codebook, to keep the palette from flickering), split
+/// and re-run Lloyd iterations until the codebook reaches `palette_size`.
+fn quantize_palette(
+    colors: &[(u8, u8, u8)],
+    palette_size: usize,
+    seed: Option<&[(f32, f32, f32)]>,
+) -> Vec<(f32, f32, f32)> {
+    let palette_size = palette_size.max(1);
+
+    if colors.is_empty() {
+        return vec![(0.0, 0.0, 0.0); palette_size];
+    }
+
+    let mut centroids: Vec<(f32, f32, f32)> = match seed {
+        Some(s) if s.len() == palette_size => return lloyd_iterate(colors, s.to_vec()),
+        _ => {
+            let n = colors.len() as f32;
+            let (mut r, mut g, mut b) = (0.0, 0.0, 0.0);
+            for &(cr, cg, cb) in colors {
+                r += cr as f32;
+                g += cg as f32;
+                b += cb as f32;
+            }
+            vec![(r / n, g / n, b / n)]
+        }
+    };
+
+    const EPSILON: f32 = 1.0;
+
+    while centroids.len() < palette_size {
+        let mut split = Vec::with_capacity(centroids.len() * 2);
+        for (r, g, b) in &centroids {
+            split.push((r + EPSILON, g + EPSILON, b + EPSILON));
+            split.push((r - EPSILON, g - EPSILON, b - EPSILON));
+        }
+        centroids = lloyd_iterate(colors, split);
+    }
+
+    centroids
+}
+
+/// Maximum number of blocks fed into `quantize_palette` per frame; larger
+/// grids are subsampled so Lloyd iterations stay fast.
+const PALETTE_SAMPLE_CAP: usize = 4096;
+
+/// Builds (or refines) the `--mode Indexed` codebook for one frame, reusing
+/// `prev` as the initial guess so the palette doesn't visibly jump around
+/// between frames. Centroids can still drift slightly frame to frame even
+/// for a static scene; the terminal skip-diff keys on the color this
+/// palette produces rather than the raw sample, so that drift still
+/// triggers a redraw instead of leaving a stale color on screen.
+fn compute_indexed_palette(
+    ascii_data: &[Vec<(BlockSample, char)>],
+    palette_size: u32,
+    prev: Option<&[(f32, f32, f32)]>,
+) -> Vec<(f32, f32, f32)> {
+    let blocks: Vec<(u8, u8, u8)> = ascii_data
+        .iter()
+        .flatten()
+        .map(|(sample, _)| (sample.r, sample.g, sample.b))
+        .collect();
+
+    let step = ((blocks.len() + PALETTE_SAMPLE_CAP - 1) / PALETTE_SAMPLE_CAP).max(1);
+    let subsampled: Vec<(u8, u8, u8)> = blocks.into_iter().step_by(step).collect();
+
+    quantize_palette(&subsampled, palette_size.next_power_of_two().max(1) as usize, prev)
+}
+
+/// `compute_indexed_palette`'s counterpart for `--subcell`: samples every
+/// sub-pixel of every cell instead of one averaged color per cell.
+fn compute_indexed_palette_subcell(
+    subcell_data: &[Vec<SubCell>],
+    palette_size: u32,
+    prev: Option<&[(f32, f32, f32)]>,
+) -> Vec<(f32, f32, f32)> {
+    let blocks: Vec<(u8, u8, u8)> = subcell_data
+        .iter()
+        .flatten()
+        .flat_map(|cell| (0..4).map(|i| (cell.r[i], cell.g[i], cell.b[i])))
+        .collect();
+
+    let step = ((blocks.len() + PALETTE_SAMPLE_CAP - 1) / PALETTE_SAMPLE_CAP).max(1);
+    let subsampled: Vec<(u8, u8, u8)> = blocks.into_iter().step_by(step).collect();
+
+    quantize_palette(&subsampled, palette_size.next_power_of_two().max(1) as usize, prev)
+}
+
+fn get_color(
+    sample: &BlockSample,
+    mode: &ArtMode,
+    x: usize,
+    y: usize,
+    frame_count: usize,
+    palette: Option<&[(f32, f32, f32)]>,
+) -> (u8, u8, u8) {
     match mode {
         ArtMode::Standard => (sample.r, sample.g, sample.b),
         ArtMode::Grayscale => {
@@ -315,23 +855,531 @@ fn get_color(sample: &BlockSample, mode: &ArtMode, x: usize, y: usize, frame_cou
                  (sample.r.wrapping_add(10), sample.g, sample.b.wrapping_add(10))
             }
         }
+        ArtMode::Indexed => {
+            let palette = palette.expect("Indexed mode requires a codebook");
+            let (idx, _) = nearest_centroid(palette, sample.r, sample.g, sample.b);
+            let (r, g, b) = palette[idx];
+            (r.round() as u8, g.round() as u8, b.round() as u8)
+        }
+    }
+}
+
+/// Whether the terminal has told us it supports 24-bit color, via the
+/// `COLORTERM` convention used by most emulators (`truecolor` or `24bit`).
+fn supports_truecolor() -> bool {
+    std::env::var("COLORTERM")
+        .map(|v| {
+            let v = v.to_lowercase();
+            v.contains("truecolor") || v.contains("24bit")
+        })
+        .unwrap_or(false)
+}
+
+/// Nearest xterm 256-color palette entry for `(r, g, b)`: the 6x6x6 color
+/// cube (indices 16..=231) for chromatic colors, or, when the channels are
+/// equal, the 24-step grayscale ramp (232..=255) or the cube's black/white.
+fn quantize_256(r: u8, g: u8, b: u8) -> u8 {
+    if r == g && g == b {
+        return match r {
+            0..=3 => 16,
+            247..=255 => 231,
+            v => 232 + ((v - 3) / 10).min(23),
+        };
+    }
+
+    // xterm cube levels are 0, 95, 135, 175, 215, 255; split at the midpoints.
+    let to6 = |c: u8| match c {
+        0..=47 => 0,
+        48..=114 => 1,
+        115..=154 => 2,
+        155..=194 => 3,
+        195..=234 => 4,
+        _ => 5,
+    };
+    16 + 36 * to6(r) + 6 * to6(g) + to6(b)
+}
+
+/// Picks the `crossterm` color to emit for `(r, g, b)`: 24-bit truecolor
+/// when the terminal supports it, otherwise the nearest 256-color entry.
+fn terminal_color(r: u8, g: u8, b: u8, truecolor: bool) -> Color {
+    if truecolor {
+        Color::Rgb { r, g, b }
+    } else {
+        Color::AnsiValue(quantize_256(r, g, b))
+    }
+}
+
+/// Reads the next frame without looping back to the start on end-of-stream,
+/// unlike `get_frame_data`. Returns `false` once the source is exhausted.
+fn read_frame_no_loop(
+    cam: &mut videoio::VideoCapture,
+    frame: &mut Mat,
+    flipped: bool,
+) -> opencv::Result<bool> {
+    let mut temp_frame = Mat::default();
+    cam.read(&mut temp_frame)?;
+
+    if temp_frame.empty() {
+        return Ok(false);
+    }
+
+    if flipped {
+        core::flip(&temp_frame, frame, 1)?;
+    } else {
+        *frame = temp_frame;
+    }
+
+    Ok(true)
+}
+
+const EXPORT_CELL_WIDTH: i32 = 10;
+const EXPORT_CELL_HEIGHT: i32 = 16;
+
+/// Draws one ASCII frame into a `Mat`, one glyph per cell, for `VideoWriter`
+/// or still-image export.
+fn rasterize_frame(
+    ascii_data: &[Vec<(BlockSample, char)>],
+    mode: &ArtMode,
+    frame_count: usize,
+    palette: Option<&[(f32, f32, f32)]>,
+) -> opencv::Result<Mat> {
+    let rows = ascii_data.len() as i32;
+    let cols = ascii_data.first().map(|r| r.len()).unwrap_or(0) as i32;
+
+    let mut canvas = Mat::new_rows_cols_with_default(
+        rows * EXPORT_CELL_HEIGHT,
+        cols * EXPORT_CELL_WIDTH,
+        core::CV_8UC3,
+        Scalar::all(0.0),
+    )?;
+
+    for (y, row) in ascii_data.iter().enumerate() {
+        for (x, (sample, ch)) in row.iter().enumerate() {
+            let (r, g, b) = get_color(sample, mode, x, y, frame_count, palette);
+            let origin = core::Point::new(
+                x as i32 * EXPORT_CELL_WIDTH,
+                y as i32 * EXPORT_CELL_HEIGHT + EXPORT_CELL_HEIGHT - 4,
+            );
+
+            imgproc::put_text(
+                &mut canvas,
+                &ch.to_string(),
+                origin,
+                imgproc::FONT_HERSHEY_SIMPLEX,
+                0.4,
+                Scalar::new(b as f64, g as f64, r as f64, 0.0),
+                1,
+                imgproc::LINE_8,
+                false,
+            )?;
+        }
+    }
+
+    Ok(canvas)
+}
+
+/// Offline transcoder path: renders every frame of `--input`/`--image` to
+/// a video `--output`; a still-image extension (png/jpg/jpeg/bmp) renders
+/// only the first frame. A `.txt` extension writes the plain glyph grid of
+/// the first frame (sized by `--scale`) instead of rasterizing through
+/// `rasterize_frame`.
+fn run_export_mode(mut cam: videoio::VideoCapture, args: Args) -> anyhow::Result<()> {
+    let output_path = args.output.as_deref().expect("run_export_mode requires --output");
+    let extension = Path::new(output_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let is_text = extension == "txt";
+    let is_still = is_text || matches!(extension.as_str(), "png" | "jpg" | "jpeg" | "bmp");
+
+    let char_set_str = resolve_charset(args.charset, &args.characters);
+    let mut frame = Mat::default();
+    let mut writer: Option<videoio::VideoWriter> = None;
+    let mut frame_count = 0usize;
+    let mut prev_palette: Option<Vec<(f32, f32, f32)>> = None;
+
+    loop {
+        if !read_frame_no_loop(&mut cam, &mut frame, args.flip).map_err(|e| anyhow::anyhow!(e))? {
+            break;
+        }
+
+        if frame.empty() {
+            continue;
+        }
+
+        if let Some(caption) = &args.caption {
+            burn_caption(&mut frame, caption, args.caption_pos).map_err(|e| anyhow::anyhow!(e))?;
+        }
+
+        let width = if is_text {
+            let img_w = frame.size().map_err(|e| anyhow::anyhow!(e))?.width;
+            (img_w / args.scale.max(1) as i32).max(10)
+        } else {
+            args.width
+        };
+
+        let mut ascii_data: Vec<Vec<(BlockSample, char)>> = Vec::new();
+        assign_chars(&mut ascii_data, char_set_str, &frame, width).map_err(|e| anyhow::anyhow!(e))?;
+
+        if is_text {
+            let mut text = String::new();
+            for row in &ascii_data {
+                for (_, ch) in row {
+                    text.push(*ch);
+                }
+                text.push('\n');
+            }
+            std::fs::write(output_path, text)?;
+            break;
+        }
+
+        if args.mode == ArtMode::Indexed {
+            prev_palette = Some(compute_indexed_palette(&ascii_data, args.palette_size, prev_palette.as_deref()));
+        }
+
+        let canvas = rasterize_frame(&ascii_data, &args.mode, frame_count, prev_palette.as_deref())
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        if is_still {
+            imgcodecs::imwrite(output_path, &canvas, &core::Vector::new()).map_err(|e| anyhow::anyhow!(e))?;
+            break;
+        }
+
+        if writer.is_none() {
+            let ext = Path::new(output_path).extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+            let fourcc = if ext.eq_ignore_ascii_case("avi") {
+                videoio::VideoWriter::fourcc('M', 'J', 'P', 'G')?
+            } else {
+                videoio::VideoWriter::fourcc('m', 'p', '4', 'v')?
+            };
+
+            writer = Some(videoio::VideoWriter::new(
+                output_path,
+                fourcc,
+                args.fps,
+                canvas.size().map_err(|e| anyhow::anyhow!(e))?,
+                true,
+            ).map_err(|e| anyhow::anyhow!(e))?);
+        }
+
+        writer.as_mut().unwrap().write(&canvas).map_err(|e| anyhow::anyhow!(e))?;
+        frame_count = frame_count.wrapping_add(1);
+    }
+
+    if let Some(mut writer) = writer {
+        writer.release().map_err(|e| anyhow::anyhow!(e))?;
+    }
+
+    Ok(())
+}
+
+fn html_escape(ch: char) -> String {
+    match ch {
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '&' => "&amp;".to_string(),
+        _ => ch.to_string(),
+    }
+}
+
+const WEB_EXPORT_CELL_WIDTH: u32 = 8;
+const WEB_EXPORT_CELL_HEIGHT: u32 = 14;
+
+/// Writes one frame as a self-contained SVG `<text>` grid, one colored
+/// `<tspan>` per glyph, for the `--export-web` still-image path.
+fn write_svg_frame(
+    file: &mut std::fs::File,
+    ascii_data: &[Vec<(BlockSample, char)>],
+    mode: &ArtMode,
+    palette: Option<&[(f32, f32, f32)]>,
+) -> anyhow::Result<()> {
+    let rows = ascii_data.len() as u32;
+    let cols = ascii_data.first().map(|r| r.len()).unwrap_or(0) as u32;
+
+    writeln!(
+        file,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+        cols * WEB_EXPORT_CELL_WIDTH,
+        rows * WEB_EXPORT_CELL_HEIGHT
+    )?;
+    writeln!(file, "<rect width=\"100%\" height=\"100%\" fill=\"#000000\"/>")?;
+    writeln!(
+        file,
+        "<text font-family=\"monospace\" font-size=\"{}\" xml:space=\"preserve\">",
+        WEB_EXPORT_CELL_HEIGHT
+    )?;
+
+    for (y, row) in ascii_data.iter().enumerate() {
+        write!(file, "<tspan x=\"0\" y=\"{}\">", (y as u32 + 1) * WEB_EXPORT_CELL_HEIGHT)?;
+        for (x, (sample, ch)) in row.iter().enumerate() {
+            let (r, g, b) = get_color(sample, mode, x, y, 0, palette);
+            write!(file, "<tspan fill=\"#{:02x}{:02x}{:02x}\">{}</tspan>", r, g, b, html_escape(*ch))?;
+        }
+        writeln!(file, "</tspan>")?;
+    }
+
+    writeln!(file, "</text>")?;
+    writeln!(file, "</svg>")?;
+    Ok(())
+}
+
+/// Offline export path: an image becomes a single SVG; a video or camera
+/// becomes an HTML document with one `<pre>` frame per tick and a JS
+/// timeline, flushed to disk as frames are produced.
+fn run_export_web_mode(mut cam: videoio::VideoCapture, args: Args) -> anyhow::Result<()> {
+    let path = args.export_web.as_deref().expect("run_export_web_mode requires --export-web");
+    let char_set_str = resolve_charset(args.charset, &args.characters);
+
+    let is_image = args
+        .source_path()
+        .and_then(|p| Path::new(p).extension())
+        .and_then(|e| e.to_str())
+        .map(|e| matches!(e.to_lowercase().as_str(), "png" | "jpg" | "jpeg" | "bmp" | "gif" | "webp"))
+        .unwrap_or(false);
+
+    let mut frame = Mat::default();
+
+    if is_image {
+        if !read_frame_no_loop(&mut cam, &mut frame, args.flip).map_err(|e| anyhow::anyhow!(e))? || frame.empty() {
+            return Err(anyhow::anyhow!("no frame available to export"));
+        }
+
+        if let Some(caption) = &args.caption {
+            burn_caption(&mut frame, caption, args.caption_pos).map_err(|e| anyhow::anyhow!(e))?;
+        }
+
+        let mut ascii_data: Vec<Vec<(BlockSample, char)>> = Vec::new();
+        assign_chars(&mut ascii_data, char_set_str, &frame, args.width).map_err(|e| anyhow::anyhow!(e))?;
+
+        let palette = if args.mode == ArtMode::Indexed {
+            Some(compute_indexed_palette(&ascii_data, args.palette_size, None))
+        } else {
+            None
+        };
+
+        let mut file = std::fs::File::create(path)?;
+        write_svg_frame(&mut file, &ascii_data, &args.mode, palette.as_deref())?;
+        return Ok(());
+    }
+
+    let mut file = std::fs::File::create(path)?;
+    writeln!(file, "<!doctype html>")?;
+    writeln!(
+        file,
+        "<html><head><meta charset=\"utf-8\"><style>body{{background:#000}}pre{{margin:0;font-family:monospace;line-height:1.1;white-space:pre}}</style></head><body>"
+    )?;
+    file.flush()?;
+
+    let mut frame_count = 0usize;
+    let mut prev_palette: Option<Vec<(f32, f32, f32)>> = None;
+
+    loop {
+        if !read_frame_no_loop(&mut cam, &mut frame, args.flip).map_err(|e| anyhow::anyhow!(e))? {
+            break;
+        }
+
+        if frame.empty() {
+            continue;
+        }
+
+        if let Some(caption) = &args.caption {
+            burn_caption(&mut frame, caption, args.caption_pos).map_err(|e| anyhow::anyhow!(e))?;
+        }
+
+        let mut ascii_data: Vec<Vec<(BlockSample, char)>> = Vec::new();
+        assign_chars(&mut ascii_data, char_set_str, &frame, args.width).map_err(|e| anyhow::anyhow!(e))?;
+
+        if args.mode == ArtMode::Indexed {
+            prev_palette = Some(compute_indexed_palette(&ascii_data, args.palette_size, prev_palette.as_deref()));
+        }
+
+        write!(file, "<pre id=\"f{}\" style=\"display:none\">", frame_count)?;
+        for (y, row) in ascii_data.iter().enumerate() {
+            for (x, (sample, ch)) in row.iter().enumerate() {
+                let (r, g, b) = get_color(sample, &args.mode, x, y, frame_count, prev_palette.as_deref());
+                write!(file, "<span style=\"color:#{:02x}{:02x}{:02x}\">{}</span>", r, g, b, html_escape(*ch))?;
+            }
+            writeln!(file)?;
+        }
+        writeln!(file, "</pre>")?;
+        file.flush()?;
+
+        frame_count = frame_count.wrapping_add(1);
+    }
+
+    let delay_ms = (1000.0 / args.fps.max(1.0)) as u64;
+    writeln!(
+        file,
+        "<script>
+const frames = document.querySelectorAll('pre[id^=\"f\"]');
+let i = 0;
+if (frames.length > 0) {{
+    frames[0].style.display = 'block';
+    setInterval(() => {{
+        frames[i].style.display = 'none';
+        i = (i + 1) % frames.length;
+        frames[i].style.display = 'block';
+    }}, {});
+}}
+</script>",
+        delay_ms
+    )?;
+    writeln!(file, "</body></html>")?;
+
+    Ok(())
+}
+
+fn validate_fps(fps: f64) -> anyhow::Result<()> {
+    if !(1.0..=480.0).contains(&fps) {
+        return Err(anyhow::anyhow!("--fps must be between 1 and 480, got {}", fps));
+    }
+    Ok(())
+}
+
+/// Marks the start of one recorded frame in a `--record` file: the frame's
+/// render delay in milliseconds, followed by the frame's raw terminal bytes
+/// (ANSI color codes included) up to the next marker or end of file.
+const RECORD_FRAME_MARKER: &str = "##SHELLART_FRAME ";
+
+/// Captures rendered ASCII frames (with their inter-frame delay) to a file
+/// that `run_play_mode` can later replay. Each frame is the exact bytes that
+/// would be printed to the terminal, so playback is just writing them back.
+fn run_record_mode(mut cam: videoio::VideoCapture, args: Args) -> anyhow::Result<()> {
+    let path = args.record.as_deref().expect("run_record_mode requires --record");
+    let char_set_str = resolve_charset(args.charset, &args.characters);
+    let frame_delay_ms = (1000.0 / args.fps) as u64;
+    let duration_limit = args.duration.map(std::time::Duration::from_millis);
+
+    let mut file = std::fs::File::create(path)?;
+    let mut frame = Mat::default();
+    let mut frame_count = 0usize;
+    let mut prev_palette: Option<Vec<(f32, f32, f32)>> = None;
+    let session_start = std::time::Instant::now();
+
+    loop {
+        if let Some(limit) = duration_limit {
+            if session_start.elapsed() >= limit {
+                break;
+            }
+        }
+
+        get_frame_data(&mut cam, &mut frame, args.flip).map_err(|e| anyhow::anyhow!(e))?;
+
+        if frame.empty() {
+            continue;
+        }
+
+        if let Some(caption) = &args.caption {
+            burn_caption(&mut frame, caption, args.caption_pos).map_err(|e| anyhow::anyhow!(e))?;
+        }
+
+        let mut ascii_data: Vec<Vec<(BlockSample, char)>> = Vec::new();
+        assign_chars(&mut ascii_data, char_set_str, &frame, args.width).map_err(|e| anyhow::anyhow!(e))?;
+
+        if args.mode == ArtMode::Indexed {
+            prev_palette = Some(compute_indexed_palette(&ascii_data, args.palette_size, prev_palette.as_deref()));
+        }
+
+        writeln!(file, "{}{}", RECORD_FRAME_MARKER, frame_delay_ms)?;
+        for (y, row) in ascii_data.iter().enumerate() {
+            for (x, (sample, ch)) in row.iter().enumerate() {
+                let (r, g, b) = get_color(sample, &args.mode, x, y, frame_count, prev_palette.as_deref());
+                write!(file, "\x1b[38;2;{};{};{}m{}", r, g, b, ch)?;
+            }
+            writeln!(file, "\x1b[0m")?;
+        }
+        file.flush()?;
+
+        frame_count = frame_count.wrapping_add(1);
+        std::thread::sleep(std::time::Duration::from_millis(frame_delay_ms));
+    }
+
+    Ok(())
+}
+
+/// Sleeps for `duration`, polling for a quit keypress so playback can be
+/// interrupted early. Returns `true` if the user asked to quit.
+fn sleep_or_quit(duration: std::time::Duration) -> anyhow::Result<bool> {
+    let deadline = std::time::Instant::now() + duration;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        if remaining.is_zero() {
+            return Ok(false);
+        }
+
+        if event::poll(remaining.min(std::time::Duration::from_millis(20)))? {
+            if let Event::Key(key) = event::read()? {
+                if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    return Ok(true);
+                }
+            }
+        }
     }
 }
 
+/// Replays a `--record` file to the terminal, honoring each frame's stored
+/// delay so the animation matches the original capture's timing.
+fn run_play_mode(args: Args) -> anyhow::Result<()> {
+    let path = args.play.as_deref().expect("run_play_mode requires --play");
+    let content = std::fs::read_to_string(path)?;
+
+    let mut stdout = stdout();
+    terminal::enable_raw_mode()?;
+    stdout.execute(terminal::EnterAlternateScreen)?;
+    stdout.execute(cursor::Hide)?;
+
+    for recorded_frame in content.split(RECORD_FRAME_MARKER).skip(1) {
+        let (delay_field, body) = recorded_frame.split_once('\n').unwrap_or((recorded_frame, ""));
+        let delay_ms: u64 = delay_field.trim().parse().unwrap_or(0);
+
+        stdout.queue(terminal::Clear(terminal::ClearType::All))?;
+        stdout.queue(cursor::MoveTo(0, 0))?;
+        write!(stdout, "{}", body.replace('\n', "\r\n"))?;
+        stdout.flush()?;
+
+        if sleep_or_quit(std::time::Duration::from_millis(delay_ms))? {
+            break;
+        }
+    }
+
+    stdout.execute(cursor::Show)?;
+    stdout.execute(terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    Ok(())
+}
+
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
-    
-    let cam = if let Some(input_path) = &args.input {
+
+    validate_fps(args.fps)?;
+
+    if args.charset == CharSet::Custom {
+        validate_custom_chars(args.characters.as_deref().unwrap_or(""), args.mode)?;
+    }
+
+    if args.play.is_some() {
+        return run_play_mode(args);
+    }
+
+    let cam = if let Some(input_path) = args.source_path() {
         videoio::VideoCapture::from_file(input_path, videoio::CAP_ANY)?
     } else {
         videoio::VideoCapture::new(args.device, videoio::CAP_ANY)?
     };
 
     if !cam.is_opened().map_err(|e| anyhow::anyhow!(e))? {
-        return Err(anyhow::anyhow!("Could not open input: {:?}", args.input.as_deref().unwrap_or("camera")));
+        return Err(anyhow::anyhow!("Could not open input: {:?}", args.source_path().unwrap_or("camera")));
     }
 
-    if args.terminal {
+    if args.record.is_some() {
+        run_record_mode(cam, args)?;
+    } else if args.export_web.is_some() {
+        run_export_web_mode(cam, args)?;
+    } else if args.output.is_some() {
+        run_export_mode(cam, args)?;
+    } else if args.terminal {
         run_terminal_mode(cam, args)?;
     } else {
         run_gui_mode(cam, args)?;
@@ -352,10 +1400,24 @@ fn run_terminal_mode(mut cam: videoio::VideoCapture, mut args: Args) -> anyhow::
     let mut frame_count = 0;
     let mut rng = rand::thread_rng();
 
+    // Previous frame's rendered (color, glyph) per cell, used to diff against
+    // the next frame so unchanged cells are skipped instead of being
+    // rewritten every tick. Keyed on the rendered color rather than the raw
+    // sample so modes like Rainbow/Indexed, whose output color can change
+    // even when the sample doesn't, still get redrawn.
+    let mut prev_frame: Option<Vec<Vec<((u8, u8, u8), char)>>> = None;
+    let mut prev_width = width;
+    let mut prev_mode = args.mode;
+    let mut prev_charset = args.charset;
+    let mut prev_show_ui = show_ui;
+    let mut force_redraw = true;
+    let mut prev_palette: Option<Vec<(f32, f32, f32)>> = None;
+    let truecolor = supports_truecolor();
+
     loop {
         if event::poll(std::time::Duration::from_millis(1))? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
+            match event::read()? {
+                Event::Key(key) => match key.code {
                     KeyCode::Char('q') | KeyCode::Esc => break,
                     KeyCode::Char('m') => args.mode = args.mode.next(),
                     KeyCode::Char('c') => args.charset = args.charset.next(),
@@ -363,7 +1425,9 @@ fn run_terminal_mode(mut cam: videoio::VideoCapture, mut args: Args) -> anyhow::
                     KeyCode::Char('-') | KeyCode::Char('_') => width = (width - 2).max(10),
                     KeyCode::Char('h') => show_ui = !show_ui,
                     _ => {}
-                }
+                },
+                Event::Resize(_, _) => force_redraw = true,
+                _ => {}
             }
         }
 
@@ -373,29 +1437,118 @@ fn run_terminal_mode(mut cam: videoio::VideoCapture, mut args: Args) -> anyhow::
             continue;
         }
 
-        let char_set_str = args.charset.get_chars();
-        let char_vec: Vec<char> = char_set_str.chars().collect();
-        let mut ascii_data: Vec<Vec<(BlockSample, char)>> = Vec::new();
-        assign_chars(&mut ascii_data, char_set_str, &frame, width).map_err(|e| anyhow::anyhow!(e))?;
+        if let Some(caption) = &args.caption {
+            burn_caption(&mut frame, caption, args.caption_pos).map_err(|e| anyhow::anyhow!(e))?;
+        }
 
-        // Reset cursor to top-left
-        stdout.queue(cursor::MoveTo(0, 0))?;
+        // Geometry-invalidating changes can't be diffed against the old buffer.
+        if width != prev_width || args.mode != prev_mode || args.charset != prev_charset {
+            force_redraw = true;
+            prev_width = width;
+            prev_mode = args.mode;
+            prev_charset = args.charset;
+        }
 
-        for (y, row) in ascii_data.iter().enumerate() {
-            for (x, (sample, ch)) in row.iter().enumerate() {
-                
-                let (r, g, b) = get_color(sample, &args.mode, x, y, frame_count);
-                
-                let final_char = if args.mode == ArtMode::Glitch && rng.gen_bool(0.02) {
-                    char_vec[rng.gen_range(0..char_vec.len())]
-                } else {
-                    *ch
-                };
-
-                stdout.queue(SetForegroundColor(Color::Rgb { r, g, b }))?;
-                stdout.queue(Print(final_char))?;
+        // Hiding the overlay would otherwise leave its text on screen.
+        if show_ui != prev_show_ui {
+            stdout.queue(terminal::Clear(terminal::ClearType::All))?;
+            force_redraw = true;
+            prev_show_ui = show_ui;
+        }
+
+        if args.subcell {
+            // Quadrant glyphs pack twice the luminance info into each cell,
+            // so the coarser char-level diff buffer doesn't apply here.
+            let mut subcell_data: Vec<Vec<SubCell>> = Vec::new();
+            assign_subcells(&mut subcell_data, &frame, width).map_err(|e| anyhow::anyhow!(e))?;
+
+            if args.mode == ArtMode::Indexed {
+                prev_palette = Some(compute_indexed_palette_subcell(&subcell_data, args.palette_size, prev_palette.as_deref()));
+            }
+
+            for (y, row) in subcell_data.iter().enumerate() {
+                stdout.queue(cursor::MoveTo(0, y as u16))?;
+                for (x, cell) in row.iter().enumerate() {
+                    let (glyph, fg, bg) = subcell_glyph_and_colors(cell, &args.mode, x, y, frame_count, prev_palette.as_deref());
+                    stdout.queue(SetForegroundColor(terminal_color(fg.0, fg.1, fg.2, truecolor)))?;
+                    stdout.queue(SetBackgroundColor(terminal_color(bg.0, bg.1, bg.2, truecolor)))?;
+                    stdout.queue(Print(glyph))?;
+                }
+                stdout.queue(SetBackgroundColor(Color::Reset))?;
+            }
+
+            force_redraw = true;
+            prev_frame = None;
+        } else {
+            let char_set_str = resolve_charset(args.charset, &args.characters);
+            let char_vec: Vec<char> = char_set_str.chars().collect();
+            let mut ascii_data: Vec<Vec<(BlockSample, char)>> = Vec::new();
+            assign_chars(&mut ascii_data, char_set_str, &frame, width).map_err(|e| anyhow::anyhow!(e))?;
+
+            let threshold = skip_threshold(args.quality);
+            let prev = prev_frame.as_ref().filter(|p| !force_redraw);
+
+            if args.mode == ArtMode::Indexed {
+                prev_palette = Some(compute_indexed_palette(&ascii_data, args.palette_size, prev_palette.as_deref()));
+            }
+
+            let mut rendered: Vec<Vec<((u8, u8, u8), char)>> = Vec::with_capacity(ascii_data.len());
+
+            for (y, row) in ascii_data.iter().enumerate() {
+                let mut rendered_row = Vec::with_capacity(row.len());
+
+                for (x, (sample, ch)) in row.iter().enumerate() {
+                    let (r, g, b) = get_color(sample, &args.mode, x, y, frame_count, prev_palette.as_deref());
+
+                    let final_char = if args.mode == ArtMode::Glitch && rng.gen_bool(0.02) {
+                        char_vec[rng.gen_range(0..char_vec.len())]
+                    } else {
+                        *ch
+                    };
+
+                    if let Some(((pr, pg, pb), prev_char)) = prev.and_then(|p| p.get(y)).and_then(|r| r.get(x)) {
+                        let dr = r as i32 - *pr as i32;
+                        let dg = g as i32 - *pg as i32;
+                        let db = b as i32 - *pb as i32;
+                        let dist = dr * dr + dg * dg + db * db;
+
+                        if dist < threshold && final_char == *prev_char {
+                            rendered_row.push(((r, g, b), final_char));
+                            continue;
+                        }
+                    }
+
+                    stdout.queue(cursor::MoveTo(x as u16, y as u16))?;
+
+                    if args.color {
+                        if truecolor {
+                            write!(stdout, "\x1b[38;2;{};{};{}m{}", r, g, b, final_char)?;
+                        } else {
+                            write!(stdout, "\x1b[38;5;{}m{}", quantize_256(r, g, b), final_char)?;
+                        }
+                    } else {
+                        stdout.queue(SetForegroundColor(terminal_color(r, g, b, truecolor)))?;
+                        stdout.queue(Print(final_char))?;
+                    }
+
+                    rendered_row.push(((r, g, b), final_char));
+                }
+
+                // Reset at end of line so one row's color escape can't bleed
+                // into the next, matching a plain ANSI-escape renderer's output.
+                if args.color {
+                    write!(stdout, "\x1b[0m")?;
+                }
+
+                rendered.push(rendered_row);
             }
-            stdout.queue(Print("\r\n"))?;
+
+            // While shown, the help/status overlay repaints row 0 and the last
+            // row in full after this loop, so it needs no help from the diff
+            // buffer — only a geometry change or toggling the overlay forces a
+            // full redraw.
+            force_redraw = false;
+            prev_frame = Some(rendered);
         }
 
         if show_ui {
@@ -410,7 +1563,10 @@ fn run_terminal_mode(mut cam: videoio::VideoCapture, mut args: Args) -> anyhow::
             // Bottom Status Bar
             if term_rows > 0 {
                 stdout.queue(cursor::MoveTo(0, term_rows - 1))?;
-                let status_text = format!(" MODE: {:?} | CHARSET: {:?} | WIDTH: {} ", args.mode, args.charset, width);
+                let status_text = format!(
+                    " MODE: {:?} | CHARSET: {:?} | WIDTH: {} | QUALITY: {} ",
+                    args.mode, args.charset, width, args.quality
+                );
                 let padding_len = (term_cols as usize).saturating_sub(status_text.len());
                 let padding = " ".repeat(padding_len);
                 
@@ -440,6 +1596,12 @@ struct ShellArtApp {
     flipped: bool,
     font_size: f32,
     frame_count: usize,
+    palette_size: u32,
+    palette: Option<Vec<(f32, f32, f32)>>,
+    subcell: bool,
+    characters: Option<String>,
+    caption: Option<String>,
+    caption_pos: CaptionPos,
 }
 
 impl ShellArtApp {
@@ -452,6 +1614,12 @@ impl ShellArtApp {
             flipped: args.flip,
             font_size: 8.0,
             frame_count: 0,
+            palette_size: args.palette_size,
+            palette: None,
+            characters: args.characters,
+            subcell: args.subcell,
+            caption: args.caption,
+            caption_pos: args.caption_pos,
         })
     }
 }
@@ -464,7 +1632,8 @@ impl eframe::App for ShellArtApp {
             ui.add(egui::Slider::new(&mut self.width, 10..=400).text("Width"));
             ui.add(egui::Slider::new(&mut self.font_size, 2.0..=20.0).text("Font Size"));
             ui.checkbox(&mut self.flipped, "Flip Horizontal");
-            
+            ui.checkbox(&mut self.subcell, "Subcell (2x resolution)");
+
             ui.separator();
             ui.label("Mode:");
             ui.radio_value(&mut self.mode, ArtMode::Standard, "Standard");
@@ -476,6 +1645,7 @@ impl eframe::App for ShellArtApp {
             ui.radio_value(&mut self.mode, ArtMode::Rainbow, "Rainbow");
             ui.radio_value(&mut self.mode, ArtMode::Cga, "CGA");
             ui.radio_value(&mut self.mode, ArtMode::Glitch, "Glitch");
+            ui.radio_value(&mut self.mode, ArtMode::Indexed, "Indexed");
 
             ui.separator();
             ui.label("Charset:");
@@ -488,24 +1658,78 @@ impl eframe::App for ShellArtApp {
             ui.radio_value(&mut self.charset, CharSet::Slashed, "Slashed");
             ui.radio_value(&mut self.charset, CharSet::Light, "Light");
             ui.radio_value(&mut self.charset, CharSet::Detailed, "Detailed");
+            ui.radio_value(&mut self.charset, CharSet::Custom, "Custom");
+            if self.charset == CharSet::Custom {
+                let mut text = self.characters.clone().unwrap_or_default();
+                if ui.text_edit_singleline(&mut text).changed() {
+                    self.characters = Some(text);
+                }
+            }
+
+            ui.separator();
+            ui.label("Caption:");
+            let mut caption = self.caption.clone().unwrap_or_default();
+            if ui.text_edit_singleline(&mut caption).changed() {
+                self.caption = if caption.is_empty() { None } else { Some(caption) };
+            }
+            ui.radio_value(&mut self.caption_pos, CaptionPos::TopLeft, "Top Left");
+            ui.radio_value(&mut self.caption_pos, CaptionPos::TopRight, "Top Right");
+            ui.radio_value(&mut self.caption_pos, CaptionPos::BottomLeft, "Bottom Left");
+            ui.radio_value(&mut self.caption_pos, CaptionPos::BottomRight, "Bottom Right");
         });
 
         egui::CentralPanel::default().show(ctx, |ui| {
             let mut frame = Mat::default();
             if let Ok(_) = get_frame_data(&mut self.cam, &mut frame, self.flipped) {
                 if !frame.empty() {
+                    if let Some(caption) = &self.caption {
+                        let _ = burn_caption(&mut frame, caption, self.caption_pos);
+                    }
+                }
+
+                if !frame.empty() && self.subcell {
+                    let mut subcell_data: Vec<Vec<SubCell>> = Vec::new();
+                    if let Ok(_) = assign_subcells(&mut subcell_data, &frame, self.width) {
+                        if self.mode == ArtMode::Indexed {
+                            self.palette = Some(compute_indexed_palette_subcell(&subcell_data, self.palette_size, self.palette.as_deref()));
+                        }
+
+                        let mut job = egui::text::LayoutJob::default();
+
+                        for (y, row) in subcell_data.iter().enumerate() {
+                            for (x, cell) in row.iter().enumerate() {
+                                let (glyph, fg, bg) = subcell_glyph_and_colors(cell, &self.mode, x, y, self.frame_count, self.palette.as_deref());
+                                job.append(&glyph.to_string(), 0.0, egui::TextFormat {
+                                    font_id: egui::FontId::monospace(self.font_size),
+                                    color: egui::Color32::from_rgb(fg.0, fg.1, fg.2),
+                                    background: egui::Color32::from_rgb(bg.0, bg.1, bg.2),
+                                    ..Default::default()
+                                });
+                            }
+                            job.append("\n", 0.0, egui::TextFormat::default());
+                        }
+
+                        egui::ScrollArea::both().show(ui, |ui| {
+                            ui.label(job);
+                        });
+                    }
+                } else if !frame.empty() {
                     let mut ascii_data: Vec<Vec<(BlockSample, char)>> = Vec::new();
-                    let char_set_str = self.charset.get_chars();
+                    let char_set_str = resolve_charset(self.charset, &self.characters);
                     let char_vec: Vec<char> = char_set_str.chars().collect();
                     if let Ok(_) = assign_chars(&mut ascii_data, char_set_str, &frame, self.width) {
-                        
+
+                        if self.mode == ArtMode::Indexed {
+                            self.palette = Some(compute_indexed_palette(&ascii_data, self.palette_size, self.palette.as_deref()));
+                        }
+
                         let mut job = egui::text::LayoutJob::default();
                         let mut rng = rand::thread_rng();
-                        
+
                         for (y, row) in ascii_data.iter().enumerate() {
                             for (x, (sample, ch)) in row.iter().enumerate() {
-                                let (r, g, b) = get_color(sample, &self.mode, x, y, self.frame_count);
-                                
+                                let (r, g, b) = get_color(sample, &self.mode, x, y, self.frame_count, self.palette.as_deref());
+
                                 let final_char = if self.mode == ArtMode::Glitch && rng.gen_bool(0.02) {
                                     char_vec[rng.gen_range(0..char_vec.len())]
                                 } else {
@@ -548,3 +1772,157 @@ fn run_gui_mode(cam: videoio::VideoCapture, args: Args) -> anyhow::Result<()> {
         }),
     ).map_err(|e| anyhow::anyhow!("eframe error: {}", e))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skip_threshold_is_most_tolerant_at_quality_zero() {
+        assert_eq!(skip_threshold(0), 80);
+    }
+
+    #[test]
+    fn skip_threshold_is_zero_at_quality_100() {
+        assert_eq!(skip_threshold(100), 0);
+    }
+
+    #[test]
+    fn skip_threshold_decreases_as_quality_rises() {
+        assert!(skip_threshold(10) > skip_threshold(50));
+        assert!(skip_threshold(50) > skip_threshold(90));
+    }
+
+    #[test]
+    fn nearest_centroid_picks_closest() {
+        let centroids = [(0.0, 0.0, 0.0), (255.0, 255.0, 255.0)];
+        let (idx, _) = nearest_centroid(&centroids, 200, 200, 200);
+        assert_eq!(idx, 1);
+        let (idx, _) = nearest_centroid(&centroids, 10, 10, 10);
+        assert_eq!(idx, 0);
+    }
+
+    #[test]
+    fn lloyd_iterate_converges_to_cluster_means() {
+        let colors = [(0, 0, 0), (10, 10, 10), (240, 240, 240), (250, 250, 250)];
+        let result = lloyd_iterate(&colors, vec![(0.0, 0.0, 0.0), (255.0, 255.0, 255.0)]);
+        let lows: Vec<_> = result.iter().filter(|c| c.0 < 128.0).collect();
+        let highs: Vec<_> = result.iter().filter(|c| c.0 >= 128.0).collect();
+        assert_eq!(lows.len(), 1);
+        assert_eq!(highs.len(), 1);
+        assert!((lows[0].0 - 5.0).abs() < 1.0);
+        assert!((highs[0].0 - 245.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn quantize_palette_returns_requested_size() {
+        let colors = [(0, 0, 0), (50, 50, 50), (200, 200, 200), (255, 255, 255)];
+        let palette = quantize_palette(&colors, 4, None);
+        assert_eq!(palette.len(), 4);
+    }
+
+    #[test]
+    fn quantize_palette_handles_empty_input() {
+        let palette = quantize_palette(&[], 3, None);
+        assert_eq!(palette.len(), 3);
+    }
+
+    #[test]
+    fn quadrant_glyph_maps_known_masks() {
+        assert_eq!(quadrant_glyph(0b0000), ' ');
+        assert_eq!(quadrant_glyph(0b1000), '▘');
+        assert_eq!(quadrant_glyph(0b1100), '▀');
+        assert_eq!(quadrant_glyph(0b1111), '█');
+    }
+
+    #[test]
+    fn quadrant_glyph_falls_back_to_solid_block_for_three_quarters() {
+        assert_eq!(quadrant_glyph(0b1110), '█');
+    }
+
+    #[test]
+    fn quantize_256_maps_black_and_white_to_cube_ends() {
+        assert_eq!(quantize_256(0, 0, 0), 16);
+        assert_eq!(quantize_256(255, 255, 255), 231);
+    }
+
+    #[test]
+    fn quantize_256_uses_grayscale_ramp_for_equal_channels() {
+        let idx = quantize_256(128, 128, 128);
+        assert!((232..=255).contains(&idx));
+    }
+
+    #[test]
+    fn quantize_256_uses_color_cube_for_chromatic_input() {
+        let idx = quantize_256(255, 0, 0);
+        assert!((16..=231).contains(&idx));
+    }
+
+    #[test]
+    fn validate_custom_chars_rejects_empty() {
+        assert!(validate_custom_chars("", ArtMode::Standard).is_err());
+    }
+
+    #[test]
+    fn validate_custom_chars_rejects_too_long() {
+        let chars = "a".repeat(MAX_CUSTOM_CHARS + 1);
+        assert!(validate_custom_chars(&chars, ArtMode::Standard).is_err());
+    }
+
+    #[test]
+    fn validate_custom_chars_rejects_single_char_in_rainbow() {
+        assert!(validate_custom_chars("@", ArtMode::Rainbow).is_err());
+    }
+
+    #[test]
+    fn validate_custom_chars_allows_single_char_in_standard() {
+        assert!(validate_custom_chars("@", ArtMode::Standard).is_ok());
+    }
+
+    #[test]
+    fn resolve_charset_prefers_characters_when_custom() {
+        let chars = Some("xy".to_string());
+        assert_eq!(resolve_charset(CharSet::Custom, &chars), "xy");
+    }
+
+    #[test]
+    fn resolve_charset_falls_back_to_modern_ramp_when_custom_unset() {
+        assert_eq!(resolve_charset(CharSet::Custom, &None), " .:-=+*#%@");
+    }
+
+    #[test]
+    fn resolve_charset_ignores_characters_for_non_custom_charset() {
+        let chars = Some("xy".to_string());
+        assert_eq!(resolve_charset(CharSet::Blocks, &chars), CharSet::Blocks.get_chars());
+    }
+
+    #[test]
+    fn validate_fps_rejects_out_of_range() {
+        assert!(validate_fps(0.0).is_err());
+        assert!(validate_fps(481.0).is_err());
+    }
+
+    #[test]
+    fn validate_fps_accepts_in_range() {
+        assert!(validate_fps(1.0).is_ok());
+        assert!(validate_fps(30.0).is_ok());
+        assert!(validate_fps(480.0).is_ok());
+    }
+
+    #[test]
+    fn glyph_5x7_is_blank_for_unknown_char() {
+        assert_eq!(glyph_5x7('#'), [0u8; 7]);
+    }
+
+    #[test]
+    fn glyph_5x7_rows_fit_in_5_bits() {
+        for row in glyph_5x7('A') {
+            assert!(row <= 0b11111);
+        }
+    }
+
+    #[test]
+    fn glyph_5x7_is_case_insensitive() {
+        assert_eq!(glyph_5x7('a'), glyph_5x7('A'));
+    }
+}